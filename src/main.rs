@@ -1,16 +1,34 @@
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::str::FromStr;
-use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use serde_derive::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use tokio::sync::Mutex;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use unicode_segmentation::UnicodeSegmentation;
 use warp::http::StatusCode;
 use warp::{Filter, Reply};
 
-#[derive(Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 struct NoteId(u64);
 
+impl NoteId {
+    fn to_be_bytes(&self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    fn from_be_bytes(bytes: &[u8]) -> Self {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(bytes);
+        NoteId(u64::from_be_bytes(buf))
+    }
+}
+
 impl FromStr for NoteId {
     type Err = ();
 
@@ -28,11 +46,37 @@ struct NoteResponse {
     id: NoteId,
     title: String,
     content: String,
+    slug: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_date: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize)]
 struct NotesResponse(Vec<NoteResponse>);
 
+#[derive(Serialize)]
+struct NotesPage {
+    notes: Vec<NoteResponse>,
+    next: Option<NoteId>,
+}
+
+#[derive(Deserialize)]
+struct ListNotesQuery {
+    limit: Option<usize>,
+    start: Option<NoteId>,
+    #[serde(default)]
+    reverse: bool,
+    #[serde(default)]
+    trashed: bool,
+}
+
+#[derive(Deserialize)]
+struct DeleteNoteQuery {
+    #[serde(default)]
+    purge: bool,
+}
+
 #[derive(Deserialize)]
 struct CreateNoteRequest {
     title: String,
@@ -46,119 +90,901 @@ struct UpdateNoteRequest {
     content: Option<String>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Deserialize, Serialize)]
 struct Note {
     id: NoteId,
     title: String,
     content: String,
+    slug: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_date: Option<DateTime<Utc>>,
+}
+
+impl Note {
+    fn to_response(&self) -> NoteResponse {
+        NoteResponse {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            content: self.content.clone(),
+            slug: self.slug.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            deleted_date: self.deleted_date,
+        }
+    }
+}
+
+// Notes live in a single sled tree keyed by the big-endian bytes of their
+// `NoteId`, so iterating the tree in key order is the same as iterating
+// notes in id order. The database handle is an `Arc` internally, so it is
+// cheap to `Clone` into each filter.
+type NoteDatabase = sled::Db;
+
+fn internal_error(err: impl std::fmt::Display) -> warp::reply::Response {
+    eprintln!("database error: {}", err);
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
+
+fn get_note(db: &NoteDatabase, id: &NoteId) -> sled::Result<Option<Note>> {
+    let Some(bytes) = db.get(id.to_be_bytes())? else {
+        return Ok(None);
+    };
+    Ok(Some(bincode::deserialize(&bytes).expect("corrupt note in database")))
+}
+
+fn put_note(db: &NoteDatabase, note: &Note) -> sled::Result<()> {
+    let bytes = bincode::serialize(note).expect("note is always serializable");
+    db.insert(note.id.to_be_bytes(), bytes)?;
+    Ok(())
+}
+
+/// A live note mutation, broadcast to every `/notes/events` subscriber.
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+enum NoteEvent {
+    Created { id: NoteId, title: String, content: String },
+    Updated { id: NoteId, title: String, content: String },
+    Deleted { id: NoteId },
+}
+
+impl NoteEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            NoteEvent::Created { .. } => "created",
+            NoteEvent::Updated { .. } => "updated",
+            NoteEvent::Deleted { .. } => "deleted",
+        }
+    }
+}
+
+/// Broadcast channel of note mutations; `Sender` is cheap to clone, so it is
+/// handed to filters the same way the database and search index are.
+type NoteEventBus = broadcast::Sender<NoteEvent>;
+
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+const DEFAULT_PAGE_LIMIT: usize = 50;
+const MAX_PAGE_LIMIT: usize = DEFAULT_PAGE_LIMIT * 20;
+
+type NoteRangeIter = Box<dyn DoubleEndedIterator<Item = sled::Result<(sled::IVec, sled::IVec)>>>;
+
+/// Range over the note tree in `NoteId` order, starting just after `start`
+/// (or from an end of the key space when `start` is absent), walking
+/// backwards when `reverse` is set.
+///
+/// Boxed because the ascending and descending arms are different concrete
+/// iterator types (`sled::Iter` vs. `std::iter::Rev<sled::Iter>`).
+fn note_range(db: &NoteDatabase, start: Option<&NoteId>, reverse: bool) -> NoteRangeIter {
+    use std::ops::Bound::{Excluded, Unbounded};
+
+    match (start, reverse) {
+        (Some(start), false) => Box::new(db.range((Excluded(start.to_be_bytes()), Unbounded))),
+        (Some(start), true) => Box::new(db.range(..start.to_be_bytes()).rev()),
+        (None, false) => Box::new(db.iter()),
+        (None, true) => Box::new(db.iter().rev()),
+    }
+}
+
+/// Splits title/content text into lowercased Unicode words, the unit both
+/// indexing and querying tokenize on.
+fn tokenize(text: &str) -> Vec<String> {
+    text.unicode_words().map(|word| word.to_lowercase()).collect()
+}
+
+/// Lowercases `title`, collapses runs of non-alphanumeric characters into a
+/// single hyphen, and trims leading/trailing hyphens. Does not guarantee
+/// uniqueness; see `SlugIndex::unique_slug`.
+fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    let mut last_was_hyphen = true;
+    for ch in title.chars() {
+        if ch.is_alphanumeric() {
+            slug.extend(ch.to_lowercase());
+            last_was_hyphen = false;
+        } else if !last_was_hyphen {
+            slug.push('-');
+            last_was_hyphen = true;
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+/// Secondary index from slug to `NoteId`, so `GET /notes/by-slug/{slug}` is
+/// O(1) instead of a full table scan. Kept in sync with the sled store by
+/// the create/update/remove/restore handlers.
+#[derive(Default)]
+struct SlugIndex {
+    by_slug: HashMap<String, NoteId>,
+}
+
+type SharedSlugIndex = Arc<Mutex<SlugIndex>>;
+
+impl SlugIndex {
+    /// Returns `base`, or `base` suffixed with `-2`, `-3`, ... if `base` is
+    /// already taken.
+    fn unique_slug(&self, base: &str) -> String {
+        if !self.by_slug.contains_key(base) {
+            return base.to_string();
+        }
+        let mut suffix = 2;
+        loop {
+            let candidate = format!("{base}-{suffix}");
+            if !self.by_slug.contains_key(&candidate) {
+                return candidate;
+            }
+            suffix += 1;
+        }
+    }
+}
+
+/// An in-memory inverted index over note titles and content, used to serve
+/// `/notes/search` without round-tripping through the database for every
+/// query. Kept in sync with the sled store by the create/update/remove/restore
+/// handlers.
+#[derive(Default)]
+struct SearchIndex {
+    /// term -> (note id -> number of occurrences of that term in the note)
+    postings: HashMap<String, HashMap<NoteId, usize>>,
+    /// note id -> distinct terms it contains, so a note can be removed from
+    /// every posting list it appears in without re-tokenizing its content.
+    doc_terms: HashMap<NoteId, HashSet<String>>,
 }
 
-type NoteDatabase = Arc<Mutex<Vec<Note>>>;
+type SharedSearchIndex = Arc<Mutex<SearchIndex>>;
+
+impl SearchIndex {
+    fn index_note(&mut self, note: &Note) {
+        self.deindex_note(&note.id);
 
-static NEXT_NOTE_ID: AtomicU64 = AtomicU64::new(1);
+        let mut terms = HashSet::new();
+        for token in tokenize(&note.title).into_iter().chain(tokenize(&note.content)) {
+            *self
+                .postings
+                .entry(token.clone())
+                .or_default()
+                .entry(note.id.clone())
+                .or_insert(0) += 1;
+            terms.insert(token);
+        }
+        self.doc_terms.insert(note.id.clone(), terms);
+    }
 
-async fn list_notes(db: NoteDatabase) -> Result<impl Reply, Infallible> {
-    let db = db.lock().await;
+    fn deindex_note(&mut self, id: &NoteId) {
+        let Some(terms) = self.doc_terms.remove(id) else {
+            return;
+        };
+        for term in terms {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(id);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
 
-    let notes = db
-        .iter()
-        .map(|note| NoteResponse {
-            id: note.id.clone(),
-            title: note.title.to_owned(),
-            content: note.content.to_owned(),
-        })
-        .collect();
+    fn matching_terms(&self, prefix: &str) -> Vec<&String> {
+        self.postings.keys().filter(|term| term.starts_with(prefix)).collect()
+    }
+
+    /// Returns note ids that match every query token (AND across tokens),
+    /// ranked by descending TF-IDF score. When `prefix_mode` is set, the
+    /// last token matches any indexed term sharing its prefix (OR within
+    /// that token) so callers can search as-you-type.
+    fn search(&self, query_tokens: &[String], prefix_mode: bool) -> Vec<(NoteId, f64)> {
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let total_notes = self.doc_terms.len() as f64;
+
+        let groups: Vec<Vec<&String>> = query_tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| {
+                if prefix_mode && i == query_tokens.len() - 1 {
+                    self.matching_terms(token)
+                } else {
+                    self.postings.get_key_value(token).map(|(term, _)| vec![term]).unwrap_or_default()
+                }
+            })
+            .collect();
+
+        if groups.iter().any(Vec::is_empty) {
+            return Vec::new();
+        }
 
-    Ok(warp::reply::json(&NotesResponse(notes)))
+        let mut candidates: Option<HashSet<NoteId>> = None;
+        for group in &groups {
+            let union: HashSet<NoteId> = group
+                .iter()
+                .filter_map(|term| self.postings.get(*term))
+                .flat_map(|docs| docs.keys().cloned())
+                .collect();
+            candidates = Some(match candidates {
+                None => union,
+                Some(prev) => prev.intersection(&union).cloned().collect(),
+            });
+        }
+
+        let mut scored: Vec<(NoteId, f64)> = candidates
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| {
+                let score = groups
+                    .iter()
+                    .flatten()
+                    .filter_map(|term| {
+                        let docs = self.postings.get(*term)?;
+                        let tf = *docs.get(&id)? as f64;
+                        // +1 smoothing keeps idf positive even when a term
+                        // appears in every candidate document, so ties don't
+                        // collapse to a zero score and fall back to arbitrary
+                        // HashSet ordering.
+                        let idf = (1.0 + total_notes / docs.len() as f64).ln();
+                        Some(tf * idf)
+                    })
+                    .sum();
+                (id, score)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<usize>,
+    #[serde(default)]
+    prefix: bool,
 }
 
-async fn create_note(db: NoteDatabase, req: CreateNoteRequest) -> Result<impl Reply, Infallible> {
+async fn list_notes(query: ListNotesQuery, db: NoteDatabase) -> Result<impl Reply, Infallible> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+
+    let mut notes = Vec::with_capacity(limit);
+    let mut next = None;
+    for entry in note_range(&db, query.start.as_ref(), query.reverse) {
+        let (_, value) = match entry {
+            Ok(entry) => entry,
+            Err(err) => return Ok(internal_error(err)),
+        };
+        let note: Note = bincode::deserialize(&value).expect("corrupt note in database");
+        if note.deleted_date.is_some() != query.trashed {
+            continue;
+        }
+        if notes.len() == limit {
+            // `start` is exclusive, so the cursor is the last id returned.
+            next = notes.last().map(|last: &NoteResponse| last.id.clone());
+            break;
+        }
+        notes.push(note.to_response());
+    }
+
+    Ok(warp::reply::json(&NotesPage { notes, next }).into_response())
+}
+
+async fn search_notes(
+    query: SearchQuery,
+    db: NoteDatabase,
+    index: SharedSearchIndex,
+) -> Result<impl Reply, Infallible> {
+    let tokens = tokenize(&query.q);
+    let ranked = {
+        let index = index.lock().await;
+        index.search(&tokens, query.prefix)
+    };
+
+    let limit = query.limit.unwrap_or(ranked.len());
+    let mut notes = Vec::with_capacity(limit.min(ranked.len()));
+    for (id, _score) in ranked.into_iter().take(limit) {
+        match get_note(&db, &id) {
+            Ok(Some(note)) => notes.push(note.to_response()),
+            Ok(None) => {} // removed from the database after the index was last updated
+            Err(err) => return Ok(internal_error(err)),
+        }
+    }
+
+    Ok(warp::reply::json(&NotesResponse(notes)).into_response())
+}
+
+async fn create_note(
+    db: NoteDatabase,
+    index: SharedSearchIndex,
+    slugs: SharedSlugIndex,
+    events: NoteEventBus,
+    req: CreateNoteRequest,
+) -> Result<impl Reply, Infallible> {
+    let id = match db.generate_id() {
+        Ok(id) => NoteId(id),
+        Err(err) => return Ok(internal_error(err)),
+    };
+
+    let slug = {
+        let mut slugs = slugs.lock().await;
+        let slug = slugs.unique_slug(&slugify(&req.title));
+        slugs.by_slug.insert(slug.clone(), id.clone());
+        slug
+    };
+
+    let now = Utc::now();
     let new_note = Note {
-        id: NoteId(NEXT_NOTE_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)),
+        id,
         title: req.title,
         content: req.content,
+        slug,
+        created_at: now,
+        updated_at: now,
+        deleted_date: None,
     };
 
-    let mut db = db.lock().await;
-    db.push(new_note.clone());
-
-    let body = warp::reply::json(&NoteResponse {
-        id: new_note.id,
-        title: new_note.title,
-        content: new_note.content,
+    if let Err(err) = put_note(&db, &new_note) {
+        return Ok(internal_error(err));
+    }
+    index.lock().await.index_note(&new_note);
+    let _ = events.send(NoteEvent::Created {
+        id: new_note.id.clone(),
+        title: new_note.title.clone(),
+        content: new_note.content.clone(),
     });
-    Ok(warp::reply::with_status(body, StatusCode::CREATED))
+
+    let body = warp::reply::json(&new_note.to_response());
+    Ok(warp::reply::with_status(body, StatusCode::CREATED).into_response())
 }
 
-async fn update_note(id: NoteId, db: NoteDatabase, req: UpdateNoteRequest) -> Result<impl Reply, Infallible> {
+async fn update_note(
+    id: NoteId,
+    db: NoteDatabase,
+    index: SharedSearchIndex,
+    slugs: SharedSlugIndex,
+    events: NoteEventBus,
+    req: UpdateNoteRequest,
+) -> Result<impl Reply, Infallible> {
     if req.title.is_none() && req.content.is_none() {
         return Ok(StatusCode::UNPROCESSABLE_ENTITY.into_response());
     }
 
-    let mut db = db.lock().await;
-    let note_idx = db.iter().position(|note| note.id == id);
-    let note = match note_idx.and_then(|idx| db.get_mut(idx)) {
-        Some(note) => note,
-        None => return Ok(StatusCode::NOT_FOUND.into_response()),
+    let mut note = match get_note(&db, &id) {
+        Ok(Some(note)) => note,
+        Ok(None) => return Ok(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => return Ok(internal_error(err)),
     };
 
+    let mut title_changed = false;
     if let Some(new_title) = req.title {
+        title_changed = new_title != note.title;
         note.title = new_title;
     }
     if let Some(new_content) = req.content {
         note.content = new_content;
     }
-    
+    note.updated_at = Utc::now();
+
+    // A trashed note's slug was freed by `remove_note` and is deliberately
+    // left unregistered until `restore_note` reclaims it, so skip
+    // re-deriving/re-registering the slug for edits made while trashed.
+    if title_changed && note.deleted_date.is_none() {
+        let mut slugs = slugs.lock().await;
+        slugs.by_slug.remove(&note.slug);
+        note.slug = slugs.unique_slug(&slugify(&note.title));
+        slugs.by_slug.insert(note.slug.clone(), note.id.clone());
+    }
+
+    if let Err(err) = put_note(&db, &note) {
+        return Ok(internal_error(err));
+    }
+    {
+        let mut index = index.lock().await;
+        if note.deleted_date.is_none() {
+            index.index_note(&note);
+        } else {
+            index.deindex_note(&note.id);
+        }
+    }
+    let _ = events.send(NoteEvent::Updated {
+        id: note.id.clone(),
+        title: note.title.clone(),
+        content: note.content.clone(),
+    });
+
     Ok(StatusCode::NO_CONTENT.into_response())
 }
 
-async fn remove_note(id: NoteId, db: NoteDatabase) -> Result<impl Reply, Infallible> {
-    let mut db = db.lock().await;
-    let old_len = db.len();
-    db.retain(|note| note.id != id);
+async fn remove_note(
+    id: NoteId,
+    query: DeleteNoteQuery,
+    db: NoteDatabase,
+    index: SharedSearchIndex,
+    slugs: SharedSlugIndex,
+    events: NoteEventBus,
+) -> Result<impl Reply, Infallible> {
+    if query.purge {
+        let existing = match get_note(&db, &id) {
+            Ok(note) => note,
+            Err(err) => return Ok(internal_error(err)),
+        };
+        return match db.remove(id.to_be_bytes()) {
+            Ok(Some(_)) => {
+                index.lock().await.deindex_note(&id);
+                if let Some(note) = existing {
+                    let mut slugs = slugs.lock().await;
+                    if slugs.by_slug.get(&note.slug) == Some(&id) {
+                        slugs.by_slug.remove(&note.slug);
+                    }
+                }
+                let _ = events.send(NoteEvent::Deleted { id });
+                Ok(StatusCode::NO_CONTENT.into_response())
+            }
+            Ok(None) => Ok(StatusCode::NOT_FOUND.into_response()),
+            Err(err) => Ok(internal_error(err)),
+        };
+    }
 
-    if old_len != db.len() {
-        Ok(StatusCode::NO_CONTENT.into_response())
-    } else {
-        Ok(StatusCode::NOT_FOUND.into_response())
+    let mut note = match get_note(&db, &id) {
+        Ok(Some(note)) => note,
+        Ok(None) => return Ok(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => return Ok(internal_error(err)),
+    };
+
+    let now = Utc::now();
+    note.deleted_date = Some(now);
+    note.updated_at = now;
+
+    if let Err(err) = put_note(&db, &note) {
+        return Ok(internal_error(err));
+    }
+    index.lock().await.deindex_note(&id);
+    {
+        let mut slugs = slugs.lock().await;
+        if slugs.by_slug.get(&note.slug) == Some(&id) {
+            slugs.by_slug.remove(&note.slug);
+        }
     }
+    let _ = events.send(NoteEvent::Deleted { id });
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Streams live note mutations as Server-Sent Events. A lagged receiver
+/// (the subscriber fell behind the broadcast channel's buffer) surfaces as a
+/// `resync` event so clients know to re-fetch and can't silently miss
+/// mutations.
+async fn stream_note_events(events: NoteEventBus) -> Result<impl Reply, Infallible> {
+    let stream = BroadcastStream::new(events.subscribe()).map(|result| {
+        let event = match result {
+            Ok(event) => warp::sse::Event::default()
+                .event(event.name())
+                .json_data(&event)
+                .unwrap_or_else(|_| warp::sse::Event::default()),
+            Err(BroadcastStreamRecvError::Lagged(_)) => warp::sse::Event::default().event("resync"),
+        };
+        Ok::<_, Infallible>(event)
+    });
+
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(stream)))
+}
+
+async fn restore_note(
+    id: NoteId,
+    db: NoteDatabase,
+    index: SharedSearchIndex,
+    slugs: SharedSlugIndex,
+) -> Result<impl Reply, Infallible> {
+    let mut note = match get_note(&db, &id) {
+        Ok(Some(note)) if note.deleted_date.is_some() => note,
+        Ok(_) => return Ok(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => return Ok(internal_error(err)),
+    };
+
+    note.deleted_date = None;
+    note.updated_at = Utc::now();
+
+    // The note's slug was freed while trashed (see `remove_note`), so
+    // another note may have since claimed it; pick a fresh unique slug in
+    // that case instead of colliding with it.
+    {
+        let mut slugs = slugs.lock().await;
+        let slug_taken_by_other = slugs.by_slug.get(&note.slug).is_some_and(|owner| *owner != note.id);
+        if slug_taken_by_other {
+            note.slug = slugs.unique_slug(&slugify(&note.title));
+        }
+        slugs.by_slug.insert(note.slug.clone(), note.id.clone());
+    }
+
+    if let Err(err) = put_note(&db, &note) {
+        return Ok(internal_error(err));
+    }
+    index.lock().await.index_note(&note);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn get_note_by_slug(slug: String, db: NoteDatabase, slugs: SharedSlugIndex) -> Result<impl Reply, Infallible> {
+    let id = slugs.lock().await.by_slug.get(&slug).cloned();
+    let Some(id) = id else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    match get_note(&db, &id) {
+        Ok(Some(note)) => Ok(warp::reply::json(&note.to_response()).into_response()),
+        Ok(None) => Ok(StatusCode::NOT_FOUND.into_response()),
+        Err(err) => Ok(internal_error(err)),
+    }
+}
+
+fn build_search_index(db: &NoteDatabase) -> SearchIndex {
+    let mut index = SearchIndex::default();
+    for bytes in db.iter().values() {
+        let bytes = bytes.expect("failed to read note while building search index");
+        let note: Note = bincode::deserialize(&bytes).expect("corrupt note in database");
+        if note.deleted_date.is_none() {
+            index.index_note(&note);
+        }
+    }
+    index
+}
+
+fn build_slug_index(db: &NoteDatabase) -> SlugIndex {
+    let mut index = SlugIndex::default();
+    for bytes in db.iter().values() {
+        let bytes = bytes.expect("failed to read note while building slug index");
+        let note: Note = bincode::deserialize(&bytes).expect("corrupt note in database");
+        if note.deleted_date.is_none() {
+            index.by_slug.insert(note.slug, note.id);
+        }
+    }
+    index
 }
 
 #[tokio::main]
 async fn main() {
-    let db = NoteDatabase::new(Mutex::new(Vec::new()));
+    let db_path = std::env::var("NOTE_DB_PATH").unwrap_or_else(|_| "notes.db".to_string());
+    let db = sled::open(db_path).expect("failed to open note database");
+    let search_index: SharedSearchIndex = Arc::new(Mutex::new(build_search_index(&db)));
+    let slug_index: SharedSlugIndex = Arc::new(Mutex::new(build_slug_index(&db)));
+    let (event_bus, _) = broadcast::channel::<NoteEvent>(EVENT_CHANNEL_CAPACITY);
 
     let note_database_filter = warp::any().map(move || db.clone());
+    let search_index_filter = warp::any().map(move || search_index.clone());
+    let slug_index_filter = warp::any().map(move || slug_index.clone());
+    let event_bus_filter = warp::any().map(move || event_bus.clone());
 
     let list_notes_handler = warp::path!("notes")
         .and(warp::get())
+        .and(warp::query::<ListNotesQuery>())
         .and(note_database_filter.clone())
         .and_then(list_notes);
 
+    let search_notes_handler = warp::path!("notes" / "search")
+        .and(warp::get())
+        .and(warp::query::<SearchQuery>())
+        .and(note_database_filter.clone())
+        .and(search_index_filter.clone())
+        .and_then(search_notes);
+
     let create_note_handler = warp::path!("notes")
         .and(warp::post())
         .and(note_database_filter.clone())
+        .and(search_index_filter.clone())
+        .and(slug_index_filter.clone())
+        .and(event_bus_filter.clone())
         .and(warp::body::content_length_limit(1024 * 16).and(warp::body::json()))
         .and_then(create_note);
 
     let update_note_handler = warp::path!("notes" / NoteId)
         .and(warp::patch())
         .and(note_database_filter.clone())
+        .and(search_index_filter.clone())
+        .and(slug_index_filter.clone())
+        .and(event_bus_filter.clone())
         .and(warp::body::content_length_limit(1024 * 16).and(warp::body::json()))
         .and_then(update_note);
 
     let remove_note_handler = warp::path!("notes" / NoteId)
         .and(warp::delete())
-        .and(note_database_filter)
+        .and(warp::query::<DeleteNoteQuery>())
+        .and(note_database_filter.clone())
+        .and(search_index_filter.clone())
+        .and(slug_index_filter.clone())
+        .and(event_bus_filter.clone())
         .and_then(remove_note);
 
+    let restore_note_handler = warp::path!("notes" / NoteId / "restore")
+        .and(warp::post())
+        .and(note_database_filter.clone())
+        .and(search_index_filter)
+        .and(slug_index_filter.clone())
+        .and_then(restore_note);
+
+    let note_events_handler = warp::path!("notes" / "events")
+        .and(warp::get())
+        .and(event_bus_filter)
+        .and_then(stream_note_events);
+
+    let get_note_by_slug_handler = warp::path!("notes" / "by-slug" / String)
+        .and(warp::get())
+        .and(note_database_filter)
+        .and(slug_index_filter)
+        .and_then(get_note_by_slug);
+
     let not_found_handler = warp::any().map(move || StatusCode::NOT_FOUND.into_response());
 
     let routes = list_notes_handler
+        .or(search_notes_handler)
+        .or(note_events_handler)
+        .or(get_note_by_slug_handler)
         .or(create_note_handler)
         .or(update_note_handler)
         .or(remove_note_handler)
+        .or(restore_note_handler)
         .or(not_found_handler);
 
     warp::serve(routes).run(([127, 0, 0, 1], 8080)).await;
 }
+
+#[cfg(test)]
+mod slug_index_tests {
+    use super::*;
+
+    #[test]
+    fn slugify_lowercases_and_collapses_punctuation() {
+        assert_eq!(slugify("Hello, World!!"), "hello-world");
+        assert_eq!(slugify("  Rust & WebAssembly  "), "rust-webassembly");
+    }
+
+    #[test]
+    fn returns_the_base_slug_when_unused() {
+        let index = SlugIndex::default();
+        assert_eq!(index.unique_slug("hello-world"), "hello-world");
+    }
+
+    #[test]
+    fn appends_an_incrementing_suffix_on_collision() {
+        let mut index = SlugIndex::default();
+        index.by_slug.insert("hello-world".to_string(), NoteId(1));
+        assert_eq!(index.unique_slug("hello-world"), "hello-world-2");
+
+        index.by_slug.insert("hello-world-2".to_string(), NoteId(2));
+        assert_eq!(index.unique_slug("hello-world"), "hello-world-3");
+    }
+}
+
+#[cfg(test)]
+mod soft_delete_tests {
+    use super::*;
+
+    fn temp_db() -> NoteDatabase {
+        sled::Config::new().temporary(true).open().expect("failed to open temp database")
+    }
+
+    #[test]
+    fn build_search_index_skips_trashed_notes() {
+        let db = temp_db();
+        let now = Utc::now();
+        let visible = Note {
+            id: NoteId(1),
+            title: "visible".to_string(),
+            content: String::new(),
+            slug: "visible".to_string(),
+            created_at: now,
+            updated_at: now,
+            deleted_date: None,
+        };
+        let trashed = Note {
+            id: NoteId(2),
+            title: "trashed".to_string(),
+            content: String::new(),
+            slug: "trashed".to_string(),
+            created_at: now,
+            updated_at: now,
+            deleted_date: Some(now),
+        };
+        put_note(&db, &visible).expect("failed to seed note");
+        put_note(&db, &trashed).expect("failed to seed note");
+
+        let index = build_search_index(&db);
+        assert!(!index.search(&["visible".to_string()], false).is_empty());
+        assert!(index.search(&["trashed".to_string()], false).is_empty());
+    }
+
+    #[test]
+    fn build_slug_index_skips_trashed_notes() {
+        let db = temp_db();
+        let now = Utc::now();
+        let visible = Note {
+            id: NoteId(1),
+            title: "visible".to_string(),
+            content: String::new(),
+            slug: "visible".to_string(),
+            created_at: now,
+            updated_at: now,
+            deleted_date: None,
+        };
+        let trashed = Note {
+            id: NoteId(2),
+            title: "trashed".to_string(),
+            content: String::new(),
+            slug: "trashed".to_string(),
+            created_at: now,
+            updated_at: now,
+            deleted_date: Some(now),
+        };
+        put_note(&db, &visible).expect("failed to seed note");
+        put_note(&db, &trashed).expect("failed to seed note");
+
+        let index = build_slug_index(&db);
+        assert_eq!(index.by_slug.get("visible"), Some(&NoteId(1)));
+        assert_eq!(index.by_slug.get("trashed"), None);
+    }
+}
+
+#[cfg(test)]
+mod note_range_tests {
+    use super::*;
+
+    fn temp_db() -> NoteDatabase {
+        sled::Config::new().temporary(true).open().expect("failed to open temp database")
+    }
+
+    fn seed(db: &NoteDatabase, ids: &[u64]) {
+        let now = Utc::now();
+        for &id in ids {
+            let note = Note {
+                id: NoteId(id),
+                title: String::new(),
+                content: String::new(),
+                slug: String::new(),
+                created_at: now,
+                updated_at: now,
+                deleted_date: None,
+            };
+            put_note(db, &note).expect("failed to seed note");
+        }
+    }
+
+    fn collect_ids(db: &NoteDatabase, start: Option<&NoteId>, reverse: bool) -> Vec<u64> {
+        note_range(db, start, reverse)
+            .map(|entry| NoteId::from_be_bytes(&entry.expect("range entry").0).0)
+            .collect()
+    }
+
+    #[test]
+    fn ascending_from_the_start() {
+        let db = temp_db();
+        seed(&db, &[1, 2, 3, 4]);
+
+        assert_eq!(collect_ids(&db, None, false), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn ascending_excludes_the_cursor_itself() {
+        let db = temp_db();
+        seed(&db, &[1, 2, 3, 4]);
+
+        assert_eq!(collect_ids(&db, Some(&NoteId(2)), false), vec![3, 4]);
+    }
+
+    #[test]
+    fn descending_from_the_end() {
+        let db = temp_db();
+        seed(&db, &[1, 2, 3, 4]);
+
+        assert_eq!(collect_ids(&db, None, true), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn descending_excludes_the_cursor_itself() {
+        let db = temp_db();
+        seed(&db, &[1, 2, 3, 4]);
+
+        assert_eq!(collect_ids(&db, Some(&NoteId(3)), true), vec![2, 1]);
+    }
+
+    #[test]
+    fn cursor_past_the_last_id_yields_an_empty_page() {
+        let db = temp_db();
+        seed(&db, &[1, 2]);
+
+        assert!(collect_ids(&db, Some(&NoteId(2)), false).is_empty());
+    }
+
+    #[test]
+    fn cursor_before_the_first_id_yields_an_empty_page() {
+        let db = temp_db();
+        seed(&db, &[1, 2]);
+
+        assert!(collect_ids(&db, Some(&NoteId(1)), true).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod search_index_tests {
+    use super::*;
+
+    fn note(id: u64, title: &str, content: &str) -> Note {
+        let now = Utc::now();
+        Note {
+            id: NoteId(id),
+            title: title.to_string(),
+            content: content.to_string(),
+            slug: String::new(),
+            created_at: now,
+            updated_at: now,
+            deleted_date: None,
+        }
+    }
+
+    #[test]
+    fn ranks_by_term_frequency() {
+        let mut index = SearchIndex::default();
+        index.index_note(&note(1, "rust", "rust rust rust"));
+        index.index_note(&note(2, "rust", "rust"));
+
+        let results = index.search(&["rust".to_string()], false);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, NoteId(1));
+    }
+
+    #[test]
+    fn intersects_all_query_tokens() {
+        let mut index = SearchIndex::default();
+        index.index_note(&note(1, "rust programming", ""));
+        index.index_note(&note(2, "rust", ""));
+
+        let results = index.search(&["rust".to_string(), "programming".to_string()], false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, NoteId(1));
+    }
+
+    #[test]
+    fn prefix_mode_matches_last_token_by_prefix() {
+        let mut index = SearchIndex::default();
+        index.index_note(&note(1, "rustacean", ""));
+        index.index_note(&note(2, "java", ""));
+
+        let results = index.search(&["rust".to_string()], true);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, NoteId(1));
+    }
+
+    #[test]
+    fn unmatched_token_yields_no_results() {
+        let mut index = SearchIndex::default();
+        index.index_note(&note(1, "rust", ""));
+
+        assert!(index.search(&["cobol".to_string()], false).is_empty());
+    }
+
+    #[test]
+    fn empty_query_returns_nothing() {
+        let index = SearchIndex::default();
+        assert!(index.search(&[], false).is_empty());
+    }
+}